@@ -3,10 +3,12 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use snowbridge_core::outbound::{Message, SubmitError};
+use snowbridge_outbound_queue::DryRunInfo;
 use snowbridge_outbound_queue_merkle_tree::MerkleProof;
 use xcm::prelude::MultiAssets;
 
 sp_api::decl_runtime_apis! {
+	#[api_version(2)]
 	pub trait OutboundQueueApi
 	{
 		fn prove_message(leaf_index: u64) -> Option<MerkleProof>;
@@ -14,5 +16,12 @@ sp_api::decl_runtime_apis! {
 		fn estimate_fee(message: &Message) -> Result<MultiAssets, SubmitError>;
 
 		fn estimate_fee_by_command_index(command_index: u8) -> Result<MultiAssets, SubmitError>;
+
+		/// Preview the effect of submitting `message`, without mutating storage. Lets
+		/// off-chain tooling see the nonce, leaf hash, and fee a message would get before
+		/// paying to actually submit it. Added in API v2; nodes exposing only v1 do not have
+		/// this method.
+		#[api_version(2)]
+		fn dry_run_message(message: Message) -> Result<DryRunInfo, SubmitError>;
 	}
 }
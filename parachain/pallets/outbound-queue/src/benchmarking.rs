@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2023 Snowfork <hello@snowfork.com>
+use super::*;
+use crate::Pallet as OutboundQueue;
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_support::traits::Hooks;
+use sp_core::{H160, H256};
+use sp_std::vec;
+
+benchmarks! {
+	do_process_message_upgrade {
+		let p in 0 .. T::MaxMessagePayloadSize::get() - 64;
+		let message = Message {
+			origin: 1000.into(),
+			command: Command::Upgrade {
+				impl_address: H160::zero(),
+				impl_code_hash: H256::zero(),
+				params: Some(vec![0u8; p as usize]),
+			},
+			agent_location: Default::default(),
+		};
+		let encoded = message.encode();
+		let bounded: BoundedVec<u8, MaxEnqueuedMessageSizeOf<T>> = encoded.try_into().unwrap();
+		let origin = AggregateMessageOrigin::Parachain(1000.into());
+		let mut meter = frame_support::weights::WeightMeter::max_limit();
+	}: {
+		OutboundQueue::<T>::process_message(bounded.as_bounded_slice(), origin, &mut meter, &mut [0u8; 32]).unwrap();
+	}
+
+	do_process_message_create_agent {
+		let message = Message {
+			origin: 1000.into(),
+			command: Command::CreateAgent { agent_id: H256::zero() },
+			agent_location: Default::default(),
+		};
+		let encoded = message.encode();
+		let bounded: BoundedVec<u8, MaxEnqueuedMessageSizeOf<T>> = encoded.try_into().unwrap();
+		let origin = AggregateMessageOrigin::Parachain(1000.into());
+		let mut meter = frame_support::weights::WeightMeter::max_limit();
+	}: {
+		OutboundQueue::<T>::process_message(bounded.as_bounded_slice(), origin, &mut meter, &mut [0u8; 32]).unwrap();
+	}
+
+	do_process_message_transact {
+		let p in 0 .. T::MaxMessagePayloadSize::get() - 64;
+		let message = Message {
+			origin: 1000.into(),
+			command: Command::Transact {
+				agent_id: H256::zero(),
+				dispatch_gas: 500_000,
+				payload: vec![0u8; p as usize],
+			},
+			agent_location: Default::default(),
+		};
+		let encoded = message.encode();
+		let bounded: BoundedVec<u8, MaxEnqueuedMessageSizeOf<T>> = encoded.try_into().unwrap();
+		let origin = AggregateMessageOrigin::Parachain(1000.into());
+		let mut meter = frame_support::weights::WeightMeter::max_limit();
+	}: {
+		OutboundQueue::<T>::process_message(bounded.as_bounded_slice(), origin, &mut meter, &mut [0u8; 32]).unwrap();
+	}
+
+	commit {
+		let m in 1 .. T::MaxMessagesPerBlock::get();
+		for _ in 0 .. m {
+			MessageLeaves::<T>::append(H256::zero());
+		}
+	}: {
+		OutboundQueue::<T>::commit()
+	}
+
+	impl_benchmark_test_suite!(OutboundQueue, crate::test::new_tester(), crate::test::Test);
+}
@@ -0,0 +1,424 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2023 Snowfork <hello@snowfork.com>
+//! # Outbound Queue
+//!
+//! Accumulates messages to be sent to Ethereum. Each block, the accumulated messages are
+//! combined into a Merkle tree, and the root is inserted into the block digest so that it can
+//! later be relayed to the Gateway contract, along with a Merkle proof for an individual
+//! message.
+//!
+//! # Message Submission
+//!
+//! Before a message can be queued, a caller must first use [`Pallet::validate`] to obtain a
+//! [`Ticket`]. The ticket can then be handed to [`Pallet::submit`], which enqueues the message
+//! with `Config::MessageQueue` for processing in a later block. [`Pallet::estimate_fee`] can be
+//! used independently to quote the delivery fee for a message.
+//!
+//! Messages are not committed immediately. Instead each is hashed into [`MessageLeaves`] as it
+//! is processed, and at `on_finalize` the accumulated leaves are combined into a Merkle root
+//! which is written to the block digest.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+mod types;
+pub mod weights;
+
+#[cfg(test)]
+mod test;
+
+pub use types::{Command, DryRunInfo, Message, SubmitError};
+pub use weights::WeightInfo;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	dispatch::DispatchClass,
+	traits::{Currency, EnqueueMessage, Get, ProcessMessage, ProcessMessageError},
+	weights::{Weight, WeightMeter},
+	BoundedVec, PalletId,
+};
+use polkadot_parachain_primitives::primitives::Id as ParaId;
+use scale_info::TypeInfo;
+use sp_core::H256;
+use sp_runtime::{
+	traits::{Convert, Hash},
+	DigestItem, RuntimeDebug,
+};
+use sp_std::prelude::*;
+use xcm::prelude::{Fungible, MultiAsset, MultiAssetId, MultiAssets, MultiLocation};
+use xcm_executor::traits::ConvertLocation;
+
+use snowbridge_outbound_queue_merkle_tree::merkle_root;
+
+pub use pallet::*;
+
+pub const LOG_TARGET: &str = "snowbridge-outbound-queue";
+
+/// Aggregate message origin for `Config::MessageQueue`, distinguishing messages enqueued by
+/// different parachains so that one parachain's backlog cannot starve another's.
+#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum AggregateMessageOrigin {
+	Parachain(ParaId),
+}
+
+pub type BalanceOf<T> =
+	<<T as Config>::Token as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+pub type MaxEnqueuedMessageSizeOf<T> =
+	<<T as Config>::MessageQueue as EnqueueMessage<AggregateMessageOrigin>>::MaxMessageLen;
+
+/// A validated message, ready to be handed to [`Pallet::submit`].
+#[derive(Clone, Encode, Decode, TypeInfo)]
+pub struct Ticket<T: Config> {
+	origin: ParaId,
+	message_id: H256,
+	message: BoundedVec<u8, MaxEnqueuedMessageSizeOf<T>>,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::TryRuntimeError;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Hash function used to build the Merkle tree of committed messages.
+		type Hashing: Hash<Output = H256>;
+
+		/// The queue that validated messages are submitted to for later processing.
+		type MessageQueue: EnqueueMessage<AggregateMessageOrigin>;
+
+		/// Max bytes in a command's ABI-encoded payload.
+		type MaxMessagePayloadSize: Get<u32>;
+
+		/// Max number of messages that can be committed to a single Merkle root per block.
+		type MaxMessagesPerBlock: Get<u32>;
+
+		/// The pallet's own id. This pallet does not read it; no fees are transferred to it.
+		type LocalPalletId: Get<PalletId>;
+
+		/// Converts an agent's `MultiLocation` into its sovereign `AccountId`. This pallet
+		/// does not call it: fees are quoted, not charged to any account.
+		type SovereignAccountOf: ConvertLocation<Self::AccountId>;
+
+		/// The asset used to pay delivery fees.
+		type Token: Currency<Self::AccountId>;
+
+		/// Converts an amount of Ethereum gas into this chain's fee asset, used to price
+		/// [`Command::Transact`]. Pluggable so that the gas/fee conversion rate can be tuned,
+		/// or sourced from an oracle, without changing this pallet.
+		type GasToFeeConversion: Convert<u64, BalanceOf<Self>>;
+
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_finalize(_: BlockNumberFor<T>) {
+			Self::commit();
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_: BlockNumberFor<T>) -> Result<(), TryRuntimeError> {
+			Self::do_try_state()
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A message was processed and its leaf appended to the pending Merkle tree.
+		MessageAccepted { id: H256, nonce: u64 },
+		/// The Merkle root of the messages processed this block was committed to the block
+		/// digest.
+		MessagesCommitted { root: H256, count: u32 },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The message is too large to be queued.
+		MessageTooLarge,
+	}
+
+	/// Message leaves accumulated during the current block, to be combined into a single
+	/// Merkle root at `on_finalize` and then cleared.
+	#[pallet::storage]
+	#[pallet::getter(fn message_leaves)]
+	pub(super) type MessageLeaves<T: Config> = StorageValue<_, Vec<H256>, ValueQuery>;
+
+	/// The nonce of the last message successfully processed for each origin.
+	#[pallet::storage]
+	#[pallet::getter(fn nonce)]
+	pub type Nonce<T: Config> = StorageMap<_, Twox64Concat, ParaId, u64, ValueQuery>;
+
+	/// Count of messages successfully processed for each origin, maintained independently
+	/// of [`Nonce`] (which is incremented at the same call site) purely so that
+	/// `do_try_state` has a second counter to cross-check `Nonce` against.
+	#[cfg(feature = "try-runtime")]
+	#[pallet::storage]
+	pub(super) type MessagesProcessed<T: Config> =
+		StorageMap<_, Twox64Concat, ParaId, u64, ValueQuery>;
+
+	/// The leaves committed to the Merkle root written to the block digest by the most
+	/// recent call to `commit`. Snapshotted because `commit` both clears `MessageLeaves`
+	/// and writes the digest in the same call, so by the time `do_try_state` runs
+	/// `MessageLeaves` alone can no longer tell us what, if anything, was just committed.
+	#[cfg(feature = "try-runtime")]
+	#[pallet::storage]
+	pub(super) type LastCommittedLeaves<T: Config> = StorageValue<_, Vec<H256>, ValueQuery>;
+
+	impl<T: Config> Pallet<T> {
+		/// Validate a message, returning a [`Ticket`] that can be handed to [`Pallet::submit`].
+		/// Does not mutate storage, so it is safe to call repeatedly, e.g. to quote a fee to a
+		/// user before they commit to sending a message.
+		pub fn validate(message: &Message) -> Result<Ticket<T>, SubmitError> {
+			let bounded = ensure_message_fits::<T>(message)?;
+			let encoded = bounded.clone().into_inner();
+
+			let message_id: H256 =
+				<T as Config>::Hashing::hash(&(message.origin, message.command.index(), encoded)
+					.encode());
+
+			Ok(Ticket { origin: message.origin, message_id, message: bounded })
+		}
+
+		/// Submit a validated message, enqueuing it with `Config::MessageQueue` for processing
+		/// in a later block.
+		pub fn submit(ticket: Ticket<T>) -> Result<H256, SubmitError> {
+			T::MessageQueue::enqueue_message(
+				ticket.message.as_bounded_slice(),
+				AggregateMessageOrigin::Parachain(ticket.origin),
+			);
+			Ok(ticket.message_id)
+		}
+
+		/// Estimate the delivery fee for a message.
+		pub fn estimate_fee(message: &Message) -> Result<MultiAssets, SubmitError> {
+			Self::calculate_fee(&message.command)
+		}
+
+		/// Run a message through the same checks as [`Pallet::validate`] without mutating
+		/// storage, previewing the nonce, leaf hash, ABI-encoded command, and fee that would
+		/// result from actually submitting it. The per-block message limit and weight checks
+		/// of [`Pallet::process_message`] are not run.
+		pub fn dry_run_message(message: Message) -> Result<DryRunInfo, SubmitError> {
+			ensure_message_fits::<T>(&message)?;
+
+			let command = message.command.abi_encode();
+			let leaf_hash: H256 = <T as Config>::Hashing::hash(&command);
+			let nonce = Nonce::<T>::get(message.origin).saturating_add(1);
+			let fee = Self::calculate_fee(&message.command)?;
+
+			Ok(DryRunInfo { nonce, leaf_hash, command, fee })
+		}
+
+		/// Estimate the delivery fee for a command, without requiring a full [`Message`].
+		pub fn estimate_fee_by_command_index(command_index: u8) -> Result<MultiAssets, SubmitError> {
+			match command_index {
+				0 => Self::calculate_fee(&Command::Upgrade {
+					impl_address: Default::default(),
+					impl_code_hash: Default::default(),
+					params: None,
+				}),
+				1 => Self::calculate_fee(&Command::CreateAgent { agent_id: Default::default() }),
+				2 => Self::calculate_fee(&Command::Transact {
+					agent_id: Default::default(),
+					dispatch_gas: 0,
+					payload: Default::default(),
+				}),
+				_ => Err(SubmitError::InvalidFeeConfig),
+			}
+		}
+
+		/// Combine the leaves accumulated this block into a Merkle root and write it to the
+		/// block digest. A no-op if no messages were processed this block.
+		pub(crate) fn commit() {
+			let count = MessageLeaves::<T>::decode_len().unwrap_or(0);
+			if count == 0 {
+				return
+			}
+
+			frame_system::Pallet::<T>::register_extra_weight_unchecked(
+				T::WeightInfo::commit(count as u32),
+				DispatchClass::Mandatory,
+			);
+
+			let leaves = MessageLeaves::<T>::take();
+
+			#[cfg(feature = "try-runtime")]
+			LastCommittedLeaves::<T>::put(leaves.clone());
+
+			let root = merkle_root::<<T as Config>::Hashing, _>(leaves);
+
+			<frame_system::Pallet<T>>::deposit_log(DigestItem::Other(root.encode()));
+
+			Self::deposit_event(Event::MessagesCommitted { root, count: count as u32 });
+		}
+
+		/// The weight of processing `command`, scaled by its payload length
+		/// ([`Command::payload_len`]).
+		fn process_message_weight(command: &Command) -> Weight {
+			let payload_len = command.payload_len();
+			match command {
+				Command::Upgrade { .. } => T::WeightInfo::do_process_message_upgrade(payload_len),
+				Command::CreateAgent { .. } => T::WeightInfo::do_process_message_create_agent(),
+				Command::Transact { .. } => T::WeightInfo::do_process_message_transact(payload_len),
+			}
+		}
+
+		/// The delivery fee for a command: a flat base relay cost plus, for
+		/// [`Command::Transact`], a term proportional to the caller-supplied gas limit.
+		fn calculate_fee(command: &Command) -> Result<MultiAssets, SubmitError> {
+			let base_fee: BalanceOf<T> = 1_000_000_000u32.into();
+
+			let gas_fee: BalanceOf<T> = match command {
+				Command::Transact { dispatch_gas, .. } =>
+					T::GasToFeeConversion::convert(*dispatch_gas),
+				_ => Default::default(),
+			};
+
+			Ok(MultiAssets::from(vec![MultiAsset {
+				id: MultiAssetId::Concrete(MultiLocation::here()),
+				fun: Fungible(base_fee.saturating_add(gas_fee).into()),
+			}]))
+		}
+	}
+
+	impl<T: Config> ProcessMessage for Pallet<T> {
+		type Origin = AggregateMessageOrigin;
+
+		fn process_message(
+			message: &[u8],
+			origin: Self::Origin,
+			meter: &mut WeightMeter,
+			_id: &mut [u8; 32],
+		) -> Result<bool, ProcessMessageError> {
+			let AggregateMessageOrigin::Parachain(para_id) = origin;
+
+			if MessageLeaves::<T>::decode_len().unwrap_or(0) >=
+				T::MaxMessagesPerBlock::get() as usize
+			{
+				return Err(ProcessMessageError::Yield)
+			}
+
+			let message = Message::decode(&mut &message[..])
+				.map_err(|_| ProcessMessageError::Corrupt)?;
+
+			let weight = Self::process_message_weight(&message.command);
+			if !meter.check_accrue(weight) {
+				return Err(ProcessMessageError::Overweight(weight))
+			}
+
+			let leaf_hash: H256 = <T as Config>::Hashing::hash(&message.command.abi_encode());
+			MessageLeaves::<T>::append(leaf_hash);
+
+			let nonce = Nonce::<T>::mutate(para_id, |nonce| {
+				*nonce = nonce.saturating_add(1);
+				*nonce
+			});
+
+			#[cfg(feature = "try-runtime")]
+			MessagesProcessed::<T>::mutate(para_id, |count| *count = count.saturating_add(1));
+
+			Self::deposit_event(Event::MessageAccepted { id: leaf_hash, nonce });
+
+			Ok(true)
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	impl<T: Config> Pallet<T> {
+		/// Cross-check the invariants that `process_message`, `commit`, and `on_finalize` are
+		/// expected to uphold, for use in try-runtime chain-upgrade dry runs.
+		pub fn do_try_state() -> Result<(), TryRuntimeError> {
+			let leaves = MessageLeaves::<T>::get();
+
+			ensure!(
+				leaves.len() <= T::MaxMessagesPerBlock::get() as usize,
+				log_and_error::<T>("MessageLeaves exceeds MaxMessagesPerBlock", &leaves)
+			);
+
+			// DEVIATION FROM REQUEST Mic92/snowbridge#chunk1-1, invariant (3): the request
+			// asks for a check that `MessageLeaves` contains no duplicate leaf hashes. That
+			// check is intentionally NOT implemented here, because `Command::abi_encode`
+			// excludes the submitting origin and nonce, so two parachains (or the same
+			// parachain twice) submitting an identical command legitimately produce
+			// identical leaves — see `submit_messages_from_multiple_origins_and_commit`,
+			// which does this on purpose. A literal duplicate-hash check would flag that
+			// legitimate state as corruption. This deviation needs requester/maintainer
+			// sign-off (flagged in the PR description, not just here) before it can be
+			// considered resolved rather than silently dropped.
+
+			// `commit` clears `MessageLeaves` and writes the digest root in the same call,
+			// so depending on exactly when this hook runs relative to `on_finalize`,
+			// `MessageLeaves` alone cannot tell us whether this block committed a root.
+			// Instead, check for this pallet's own digest item directly: if present, it must
+			// match the root recomputed from `LastCommittedLeaves`, which `commit` snapshots
+			// in the same call that writes the digest.
+			if let Some(root) =
+				frame_system::Pallet::<T>::digest().logs().iter().find_map(|item| item.as_other())
+			{
+				let committed_leaves = LastCommittedLeaves::<T>::get();
+				let expected_root = merkle_root::<<T as Config>::Hashing, _>(committed_leaves.clone());
+				ensure!(
+					root == expected_root.as_bytes(),
+					log_and_error::<T>(
+						"committed digest root does not match the last committed leaves",
+						&committed_leaves
+					)
+				);
+			}
+
+			for (origin, nonce) in Nonce::<T>::iter() {
+				let processed = MessagesProcessed::<T>::get(origin);
+				if nonce == 0 || nonce != processed {
+					log::warn!(
+						target: LOG_TARGET,
+						"try_state: origin {:?} has Nonce {} but MessagesProcessed {}, expected \
+						the two to be equal and non-zero",
+						origin,
+						nonce,
+						processed,
+					);
+					return Err("OutboundQueue: Nonce does not match messages processed".into())
+				}
+			}
+
+			Ok(())
+		}
+	}
+}
+
+#[cfg(feature = "try-runtime")]
+fn log_and_error<T: Config>(message: &str, leaves: &[H256]) -> sp_runtime::DispatchError {
+	log::warn!(target: LOG_TARGET, "try_state: {}: {:?}", message, leaves);
+	message.into()
+}
+
+/// Checks a command's ABI-encoded payload against `Config::MaxMessagePayloadSize`.
+fn ensure_payload_fits<T: Config>(command: &Command) -> Result<(), SubmitError> {
+	if command.abi_encode().len() > T::MaxMessagePayloadSize::get() as usize {
+		return Err(SubmitError::MessageTooLarge)
+	}
+	Ok(())
+}
+
+/// Checks both a command's ABI-encoded payload against `Config::MaxMessagePayloadSize` and
+/// the full SCALE-encoded message against the bound `Config::MessageQueue` enqueues at,
+/// returning the bounded encoding so callers don't have to encode the message twice. Shared
+/// by [`Pallet::validate`] and [`Pallet::dry_run_message`] so a message the former would
+/// reject can never be reported as dry-runnable by the latter.
+fn ensure_message_fits<T: Config>(
+	message: &Message,
+) -> Result<BoundedVec<u8, MaxEnqueuedMessageSizeOf<T>>, SubmitError> {
+	ensure_payload_fits::<T>(&message.command)?;
+
+	message.encode().try_into().map_err(|_| SubmitError::MessageTooLarge)
+}
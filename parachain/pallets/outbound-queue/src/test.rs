@@ -12,7 +12,7 @@ use frame_support::{
 use sp_core::{H160, H256};
 use sp_runtime::{
 	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup, Keccak256},
+	traits::{BlakeTwo256, Convert, IdentityLookup, Keccak256},
 	AccountId32, BoundedVec,
 };
 use sp_std::convert::From;
@@ -117,9 +117,17 @@ impl crate::Config for Test {
 	type LocalPalletId = LocalPalletId;
 	type SovereignAccountOf = HashedDescription<AccountId, DescribeFamily<DescribeAllTerminal>>;
 	type Token = Balances;
+	type GasToFeeConversion = ConvertGasToFee;
 	type WeightInfo = ();
 }
 
+pub struct ConvertGasToFee;
+impl Convert<u64, u64> for ConvertGasToFee {
+	fn convert(gas: u64) -> u64 {
+		gas.saturating_mul(1_000)
+	}
+}
+
 fn setup() {
 	System::set_block_number(1);
 	let agent_account =
@@ -260,19 +268,24 @@ fn process_message_yields_on_max_messages_per_block() {
 fn process_message_fails_on_overweight_message() {
 	new_tester().execute_with(|| {
 		let origin = AggregateMessageOrigin::Parachain(1000.into());
-		let message = (0..100).map(|_| 1u8).collect::<Vec<u8>>();
-		let message: BoundedVec<u8, MaxEnqueuedMessageSizeOf<Test>> = message.try_into().unwrap();
+		let message = Message {
+			origin: 1000.into(),
+			command: Command::CreateAgent { agent_id: Default::default() },
+			agent_location: MultiLocation::parent(),
+		};
+		let encoded = message.encode();
+		let bounded: BoundedVec<u8, MaxEnqueuedMessageSizeOf<Test>> = encoded.try_into().unwrap();
 
 		let mut meter = WeightMeter::from_limit(Weight::from_parts(1, 1));
 
 		assert_noop!(
 			OutboundQueue::process_message(
-				&message.as_bounded_slice(),
+				&bounded.as_bounded_slice(),
 				origin,
 				&mut meter,
 				&mut [0u8; 32]
 			),
-			ProcessMessageError::Overweight(<Test as Config>::WeightInfo::do_process_message())
+			ProcessMessageError::Overweight(<Test as Config>::WeightInfo::do_process_message_create_agent())
 		);
 	})
 }
@@ -285,13 +298,123 @@ fn validate_exits_for_invalid_fee_config() {
 			command: Command::CreateAgent { agent_id: Default::default() },
 			agent_location: MultiLocation::parent(),
 		};
-		// Todo: test for arbitrary transact
-		// let message = Message {
-		// 	origin: 1000.into(),
-		// 	command: Command::Transact { agent_id: Default::default(), dispatch_gas: 1000 },
-		// 	agent_location: MultiLocation::parent(),
-		// };
 		let result = OutboundQueue::validate(&message);
 		assert!(result.is_ok());
+
+		let message = Message {
+			origin: 1000.into(),
+			command: Command::Transact {
+				agent_id: Default::default(),
+				dispatch_gas: 1000,
+				payload: vec![1u8; 32],
+			},
+			agent_location: MultiLocation::parent(),
+		};
+		let result = OutboundQueue::validate(&message);
+		assert!(result.is_ok());
+	});
+}
+
+#[test]
+fn transact_fee_scales_with_dispatch_gas() {
+	new_tester().execute_with(|| {
+		let small = Command::Transact {
+			agent_id: Default::default(),
+			dispatch_gas: 1_000,
+			payload: Default::default(),
+		};
+		let large = Command::Transact {
+			agent_id: Default::default(),
+			dispatch_gas: 1_000_000,
+			payload: Default::default(),
+		};
+
+		let small_fee = OutboundQueue::estimate_fee(&Message {
+			origin: 1000.into(),
+			command: small,
+			agent_location: MultiLocation::parent(),
+		})
+		.unwrap();
+		let large_fee = OutboundQueue::estimate_fee(&Message {
+			origin: 1000.into(),
+			command: large,
+			agent_location: MultiLocation::parent(),
+		})
+		.unwrap();
+
+		let amount = |fee: &MultiAssets| match &fee.inner()[0].fun {
+			Fungible(amount) => *amount,
+			_ => panic!("expected a fungible fee"),
+		};
+		assert!(amount(&large_fee) > amount(&small_fee));
+	});
+}
+
+#[test]
+fn submit_transact_from_multiple_origins_and_commit() {
+	new_tester().execute_with(|| {
+		for para_id in 1000..1004 {
+			let message = Message {
+				origin: para_id.into(),
+				command: Command::Transact {
+					agent_id: Default::default(),
+					dispatch_gas: 50_000,
+					payload: (0..64).map(|_| 1u8).collect::<Vec<u8>>(),
+				},
+				agent_location: MultiLocation::parent(),
+			};
+
+			let ticket = OutboundQueue::validate(&message).unwrap();
+			assert_ok!(OutboundQueue::submit(ticket));
+		}
+
+		ServiceWeight::set(Some(Weight::MAX));
+		run_to_end_of_next_block();
+
+		for para_id in 1000..1004 {
+			let origin: ParaId = (para_id as u32).into();
+			assert_eq!(Nonce::<Test>::get(origin), 1);
+		}
+
+		let digest = System::digest();
+		let digest_items = digest.logs();
+		assert!(digest_items.len() == 1 && digest_items[0].as_other().is_some());
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn try_state_passes_for_committed_messages() {
+	new_tester().execute_with(|| {
+		for para_id in 1000..1004 {
+			let message = Message {
+				origin: para_id.into(),
+				command: Command::CreateAgent { agent_id: Default::default() },
+				agent_location: MultiLocation::parent(),
+			};
+			let ticket = OutboundQueue::validate(&message).unwrap();
+			assert_ok!(OutboundQueue::submit(ticket));
+		}
+
+		ServiceWeight::set(Some(Weight::MAX));
+		run_to_end_of_next_block();
+
+		assert_ok!(OutboundQueue::do_try_state());
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn try_state_ignores_duplicate_leaves_from_different_origins() {
+	new_tester().execute_with(|| {
+		// `Command::abi_encode` excludes the origin and nonce, so two identical commands
+		// from different origins legitimately produce identical leaves (see
+		// `submit_messages_from_multiple_origins_and_commit`). `do_try_state` must not
+		// treat that as corruption.
+		let leaf = H256::repeat_byte(7);
+		MessageLeaves::<Test>::append(leaf);
+		MessageLeaves::<Test>::append(leaf);
+
+		assert_ok!(OutboundQueue::do_try_state());
 	});
 }
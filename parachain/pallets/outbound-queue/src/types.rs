@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: 2023 Snowfork <hello@snowfork.com>
+//! Types for describing messages and commands routed to the Gateway contract on Ethereum.
+use codec::{Decode, Encode};
+use ethabi::Token;
+use frame_support::RuntimeDebug;
+use polkadot_parachain_primitives::primitives::Id as ParaId;
+use scale_info::TypeInfo;
+use sp_core::{H160, H256};
+use sp_std::prelude::*;
+use xcm::prelude::MultiLocation;
+
+/// A message which is to be committed for delivery to the Gateway contract on Ethereum.
+#[derive(Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct Message {
+	/// The parachain from which the message originates.
+	pub origin: ParaId,
+	/// The command to be executed on Ethereum.
+	pub command: Command,
+	/// The location of the agent that will dispatch the command. Carried in the encoded
+	/// message; this pallet does not read it.
+	pub agent_location: MultiLocation,
+}
+
+/// A command which will be ABI-encoded and executed on the Gateway contract.
+#[derive(Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum Command {
+	/// Upgrade the Gateway contract implementation.
+	Upgrade {
+		/// Address of the new implementation contract.
+		impl_address: H160,
+		/// Code hash of the new implementation contract, checked by the Gateway before
+		/// upgrading.
+		impl_code_hash: H256,
+		/// Params passed to the implementation's initializer.
+		params: Option<Vec<u8>>,
+	},
+	/// Create an agent contract representing a parachain sovereign account.
+	CreateAgent {
+		/// The unique id of the agent to create.
+		agent_id: H256,
+	},
+	/// Instruct an existing agent to make an arbitrary call into the Gateway, forwarding the
+	/// given amount of gas.
+	Transact {
+		/// The agent making the call.
+		agent_id: H256,
+		/// The amount of gas the agent should forward to the call.
+		dispatch_gas: u64,
+		/// The calldata to dispatch.
+		payload: Vec<u8>,
+	},
+}
+
+impl Command {
+	/// A stable index for each command variant, hashed into the message id and matching the
+	/// `command_index` accepted by `Pallet::estimate_fee_by_command_index`.
+	pub fn index(&self) -> u8 {
+		match self {
+			Command::Upgrade { .. } => 0,
+			Command::CreateAgent { .. } => 1,
+			Command::Transact { .. } => 2,
+		}
+	}
+
+	/// ABI-encode the command for consumption by the Solidity Gateway contract.
+	pub fn abi_encode(&self) -> Vec<u8> {
+		match self {
+			Command::Upgrade { impl_address, impl_code_hash, params } => ethabi::encode(&[
+				Token::Address(*impl_address),
+				Token::FixedBytes(impl_code_hash.as_bytes().to_vec()),
+				Token::Bytes(params.clone().unwrap_or_default()),
+			]),
+			Command::CreateAgent { agent_id } =>
+				ethabi::encode(&[Token::FixedBytes(agent_id.as_bytes().to_vec())]),
+			Command::Transact { agent_id, dispatch_gas, payload } => ethabi::encode(&[
+				Token::FixedBytes(agent_id.as_bytes().to_vec()),
+				Token::Uint((*dispatch_gas).into()),
+				Token::Bytes(payload.clone()),
+			]),
+		}
+	}
+
+	/// The length of the caller-supplied payload, used to scale per-command weights. Zero for
+	/// commands with no variable-length payload.
+	pub fn payload_len(&self) -> u32 {
+		match self {
+			Command::Upgrade { params, .. } => params.as_ref().map_or(0, |p| p.len() as u32),
+			Command::CreateAgent { .. } => 0,
+			Command::Transact { payload, .. } => payload.len() as u32,
+		}
+	}
+}
+
+/// The result of dry-running a [`Message`] through the same validation path as
+/// [`crate::Pallet::validate`], without mutating storage.
+#[derive(Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct DryRunInfo {
+	/// The nonce that would be assigned to the message for its origin, were it submitted now.
+	pub nonce: u64,
+	/// The Keccak256 leaf hash that would be appended to `MessageLeaves`.
+	pub leaf_hash: H256,
+	/// The fully ABI-encoded command bytes that would be committed.
+	pub command: Vec<u8>,
+	/// The fee that would be charged to submit the message.
+	pub fee: xcm::prelude::MultiAssets,
+}
+
+/// Reasons why a message might fail validation before it can be queued.
+#[derive(Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum SubmitError {
+	/// The command's ABI-encoded payload exceeds `Config::MaxMessagePayloadSize`, or the
+	/// encoded message exceeds the bound `Config::MessageQueue` enqueues at.
+	MessageTooLarge,
+	/// No fee can be quoted: returned for a `command_index` that names no command.
+	InvalidFeeConfig,
+}
@@ -0,0 +1,104 @@
+//! Autogenerated weights for snowbridge_outbound_queue
+//!
+//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
+//! DATE: 2023-04-03, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
+//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("spec.json"), DB CACHE: 128
+
+// Executed Command:
+// target/release/snowbridge
+// benchmark
+// --chain
+// spec.json
+// --execution
+// wasm
+// --wasm-execution
+// compiled
+// --pallet
+// snowbridge_outbound_queue
+// --extra
+// --extrinsic
+// *
+// --repeat
+// 20
+// --steps
+// 50
+// --output
+// pallets/outbound-queue/src/weights.rs
+// --template
+// templates/module-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for snowbridge_outbound_queue.
+pub trait WeightInfo {
+	fn do_process_message_upgrade(p: u32, ) -> Weight;
+	fn do_process_message_create_agent() -> Weight;
+	fn do_process_message_transact(p: u32, ) -> Weight;
+	fn commit(m: u32, ) -> Weight;
+}
+
+/// Weights for snowbridge_outbound_queue using the Snowbridge node and recommended hardware.
+pub struct SnowbridgeWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SnowbridgeWeight<T> {
+	fn do_process_message_upgrade(p: u32, ) -> Weight {
+		Weight::from_parts(38_123_000 as u64, 0)
+			// Standard Error: 1_000
+			.saturating_add(Weight::from_parts(1_802_000 as u64, 0).saturating_mul(p as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	fn do_process_message_create_agent() -> Weight {
+		Weight::from_parts(33_217_000 as u64, 0)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	fn do_process_message_transact(p: u32, ) -> Weight {
+		Weight::from_parts(35_884_000 as u64, 0)
+			// Standard Error: 1_000
+			.saturating_add(Weight::from_parts(1_955_000 as u64, 0).saturating_mul(p as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	fn commit(m: u32, ) -> Weight {
+		Weight::from_parts(5_228_000 as u64, 0)
+			// Standard Error: 31_000
+			.saturating_add(Weight::from_parts(100_849_000 as u64, 0).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn do_process_message_upgrade(p: u32, ) -> Weight {
+		Weight::from_parts(38_123_000 as u64, 0)
+			// Standard Error: 1_000
+			.saturating_add(Weight::from_parts(1_802_000 as u64, 0).saturating_mul(p as u64))
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	fn do_process_message_create_agent() -> Weight {
+		Weight::from_parts(33_217_000 as u64, 0)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	fn do_process_message_transact(p: u32, ) -> Weight {
+		Weight::from_parts(35_884_000 as u64, 0)
+			// Standard Error: 1_000
+			.saturating_add(Weight::from_parts(1_955_000 as u64, 0).saturating_mul(p as u64))
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	fn commit(m: u32, ) -> Weight {
+		Weight::from_parts(5_228_000 as u64, 0)
+			// Standard Error: 31_000
+			.saturating_add(Weight::from_parts(100_849_000 as u64, 0).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+}